@@ -1,5 +1,5 @@
 use crate::api::jsonschema;
-use crate::api::schema::{Body, Cookie, Header, Method, Path, Query};
+use crate::api::schema::{Body, Cookie, Form, Header, Method, Path, Query};
 use crate::encore::parser::meta::v1 as meta;
 use crate::encore::parser::meta::v1::path_segment::SegmentType;
 use crate::encore::parser::schema::v1 as schema;
@@ -13,6 +13,9 @@ use std::sync::Arc;
 pub enum DefaultLoc {
     Body,
     Query,
+    /// The request body is `multipart/form-data` or
+    /// `application/x-www-form-urlencoded` rather than JSON.
+    Form,
 }
 
 impl DefaultLoc {
@@ -20,6 +23,7 @@ impl DefaultLoc {
         match self {
             DefaultLoc::Body => WireLoc::Body,
             DefaultLoc::Query => WireLoc::Query,
+            DefaultLoc::Form => WireLoc::Form,
         }
     }
 }
@@ -31,6 +35,132 @@ pub enum WireLoc {
     Header(String),
     Path,
     Cookie(String),
+    Form,
+}
+
+/// How a list-typed query or header field is joined into (or split back
+/// out of) a single wire value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollectionFormat {
+    /// Comma-separated values: `a,b,c`.
+    Csv,
+    /// Space-separated values: `a b c`.
+    Ssv,
+    /// Tab-separated values: `a\tb\tc`.
+    Tsv,
+    /// Pipe-separated values: `a|b|c`.
+    Pipes,
+    /// The key is repeated once per value: `?tags=a&tags=b`.
+    Multi,
+}
+
+impl CollectionFormat {
+    /// The default format for a list-typed field routed to the given
+    /// location, or `None` if the location doesn't support lists.
+    fn default_for(wire_loc: &WireLoc) -> Option<Self> {
+        match wire_loc {
+            WireLoc::Query => Some(CollectionFormat::Multi),
+            WireLoc::Header(_) => Some(CollectionFormat::Csv),
+            WireLoc::Body | WireLoc::Path | WireLoc::Cookie(_) | WireLoc::Form => None,
+        }
+    }
+
+    /// The separator to join/split elements on, or `None` for `Multi`
+    /// where the key itself is repeated instead.
+    pub fn separator(self) -> Option<char> {
+        match self {
+            CollectionFormat::Csv => Some(','),
+            CollectionFormat::Ssv => Some(' '),
+            CollectionFormat::Tsv => Some('\t'),
+            CollectionFormat::Pipes => Some('|'),
+            CollectionFormat::Multi => None,
+        }
+    }
+}
+
+/// Returns the element type of a (possibly pointer-wrapped) list type.
+fn list_elem_type(typ: &Typ) -> Option<&Typ> {
+    match typ {
+        Typ::List(list) => list.elem.as_ref()?.typ.as_ref(),
+        Typ::Pointer(ptr) => list_elem_type(ptr.base.as_ref()?.typ.as_ref()?),
+        _ => None,
+    }
+}
+
+/// Reports whether a form field should be treated as a file part (as
+/// opposed to a plain key/value part), i.e. it carries raw bytes.
+fn is_file_field(typ: &Typ) -> bool {
+    match typ {
+        Typ::Builtin(b) => *b == schema::Builtin::Bytes as i32,
+        Typ::List(list) => list
+            .elem
+            .as_ref()
+            .and_then(|e| e.typ.as_ref())
+            .is_some_and(is_file_field),
+        Typ::Pointer(ptr) => ptr
+            .base
+            .as_ref()
+            .and_then(|b| b.typ.as_ref())
+            .is_some_and(is_file_field),
+        _ => false,
+    }
+}
+
+/// A wire format a request/response body can be serialized to or parsed
+/// from. Every codec shares the same registered JSON schema, so
+/// validation is identical regardless of which one is negotiated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BodyCodec {
+    Json,
+    FormUrlEncoded,
+    Yaml,
+    MessagePack,
+}
+
+impl BodyCodec {
+    /// The media type this codec is selected by, as it would appear in a
+    /// `Content-Type` or `Accept` header.
+    pub fn media_type(self) -> &'static str {
+        match self {
+            BodyCodec::Json => "application/json",
+            BodyCodec::FormUrlEncoded => "application/x-www-form-urlencoded",
+            BodyCodec::Yaml => "application/yaml",
+            BodyCodec::MessagePack => "application/msgpack",
+        }
+    }
+
+    fn from_media_type(media_type: &str) -> Option<Self> {
+        // Strip any `;charset=...`-style parameters before comparing.
+        let media_type = media_type.split(';').next().unwrap_or(media_type).trim();
+        [
+            BodyCodec::Json,
+            BodyCodec::FormUrlEncoded,
+            BodyCodec::Yaml,
+            BodyCodec::MessagePack,
+        ]
+        .into_iter()
+        .find(|c| c.media_type().eq_ignore_ascii_case(media_type))
+    }
+
+    /// Picks the codec to use for a body given the set of codecs the RPC
+    /// supports and the client's `Content-Type` (or `Accept`) header,
+    /// falling back to JSON when the client sends nothing or asks for a
+    /// codec the RPC doesn't support.
+    pub fn negotiate(content_type: Option<&str>, supported: &[BodyCodec]) -> Self {
+        content_type
+            .and_then(Self::from_media_type)
+            .filter(|c| supported.contains(c))
+            .unwrap_or(BodyCodec::Json)
+    }
+}
+
+/// Resolves the media types an rpc was configured with to the `BodyCodec`s
+/// it supports, silently dropping any the runtime doesn't recognize.
+fn parse_body_codecs(media_types: &[String]) -> Vec<BodyCodec> {
+    media_types
+        .iter()
+        .filter_map(|mt| BodyCodec::from_media_type(mt))
+        .collect()
 }
 
 pub struct EncodingConfig<'a, 'b> {
@@ -42,26 +172,54 @@ pub struct EncodingConfig<'a, 'b> {
     pub supports_query: bool,
     pub supports_header: bool,
     pub supports_path: bool,
+    pub supports_form: bool,
+    /// The body codecs the RPC accepts/produces, in addition to JSON.
+    /// JSON is always implicitly supported as the fallback.
+    pub body_codecs: Vec<BodyCodec>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SchemaUnderConstruction {
     combined: Option<usize>,
     body: Option<usize>,
     query: Option<usize>,
     header: Option<usize>,
     cookie: Option<usize>,
+    form: Option<usize>,
     rpc_path: Option<meta::Path>,
+    query_formats: HashMap<String, CollectionFormat>,
+    header_formats: HashMap<String, CollectionFormat>,
+    /// Maps a header struct field's registry name to its wire name (the
+    /// `Header{ name: ... }` override, if any), so consumers that only know
+    /// the registry name (e.g. the OpenAPI emitter) can recover what
+    /// actually goes on the wire.
+    header_names: HashMap<String, String>,
+    /// Same as `header_names`, for cookie fields.
+    cookie_names: HashMap<String, String>,
+    /// Name of the struct field, if any, that acts as a catch-all sink
+    /// for query params not bound to another field.
+    query_sink: Option<String>,
+    /// Name of the struct field, if any, that acts as a catch-all sink
+    /// for headers not bound to another field.
+    header_sink: Option<String>,
+    body_codecs: Vec<BodyCodec>,
 }
 
 impl SchemaUnderConstruction {
     pub fn build(self, reg: &Arc<jsonschema::Registry>) -> anyhow::Result<Schema> {
         Ok(Schema {
             combined: self.combined.map(|v| reg.schema(v)),
-            body: self.body.map(|v| Body::new(reg.schema(v))),
-            query: self.query.map(|v| Query::new(reg.schema(v))),
-            header: self.header.map(|v| Header::new(reg.schema(v))),
+            body: self
+                .body
+                .map(|v| Body::new(reg.schema(v), self.body_codecs)),
+            query: self.query.map(|v| {
+                Query::new(reg.schema(v), self.query_formats, self.query_sink)
+            }),
+            header: self.header.map(|v| {
+                Header::new(reg.schema(v), self.header_formats, self.header_sink)
+            }),
             cookie: self.cookie.map(|v| Cookie::new(reg.schema(v))),
+            form: self.form.map(|v| Form::new(reg.schema(v))),
             path: self.rpc_path.as_ref().map(Path::from_meta).transpose()?,
         })
     }
@@ -75,6 +233,7 @@ pub struct Schema {
     pub body: Option<Body>,
     pub path: Option<Path>,
     pub cookie: Option<Cookie>,
+    pub form: Option<Form>,
 }
 
 impl EncodingConfig<'_, '_> {
@@ -89,7 +248,15 @@ impl EncodingConfig<'_, '_> {
                 query: None,
                 header: None,
                 cookie: None,
+                form: None,
                 rpc_path: self.rpc_path.cloned(),
+                query_formats: HashMap::new(),
+                query_sink: None,
+                header_sink: None,
+                body_codecs: Vec::new(),
+                header_formats: HashMap::new(),
+                header_names: HashMap::new(),
+                cookie_names: HashMap::new(),
             });
         };
 
@@ -115,6 +282,13 @@ impl EncodingConfig<'_, '_> {
         let mut query: Option<jsonschema::Struct> = None;
         let mut header: Option<jsonschema::Struct> = None;
         let mut cookie: Option<jsonschema::Struct> = None;
+        let mut form: Option<jsonschema::Struct> = None;
+        let mut query_formats = HashMap::new();
+        let mut header_formats = HashMap::new();
+        let mut header_names: HashMap<String, String> = HashMap::new();
+        let mut cookie_names: HashMap<String, String> = HashMap::new();
+        let mut query_sink: Option<String> = None;
+        let mut header_sink: Option<String> = None;
 
         for f in &st.fields {
             // If it's a path field, skip it. We handle it separately in Path::from_meta.
@@ -128,10 +302,19 @@ impl EncodingConfig<'_, '_> {
             // Resolve which location the field should be in.
             let loc = f.wire.as_ref().and_then(|w| w.location.as_ref());
             let wire_loc = match loc {
-                None => self
-                    .default_loc
-                    .with_context(|| format!("no location defined for field {}", f.name))?
-                    .into_wire_loc(),
+                None => {
+                    // RPCs that opt into a form-encoded request body route
+                    // what would otherwise be the JSON body into the form
+                    // instead.
+                    let default_loc = if self.default_loc == Some(DefaultLoc::Body) && self.supports_form {
+                        Some(DefaultLoc::Form)
+                    } else {
+                        self.default_loc
+                    };
+                    default_loc
+                        .with_context(|| format!("no location defined for field {}", f.name))?
+                        .into_wire_loc()
+                }
                 Some(schema::wire_spec::Location::Header(hdr)) => {
                     WireLoc::Header(hdr.name.as_ref().unwrap_or(&f.name).clone())
                 }
@@ -141,15 +324,115 @@ impl EncodingConfig<'_, '_> {
                 }
             };
 
+            // A field marked `catch_all` absorbs every query param or
+            // header that isn't explicitly bound to another field,
+            // rather than occupying a wire slot of its own.
+            let catch_all = match loc {
+                Some(schema::wire_spec::Location::Query(q)) => q.catch_all,
+                Some(schema::wire_spec::Location::Header(h)) => h.catch_all,
+                _ => false,
+            };
+
+            if catch_all {
+                let is_map = f
+                    .typ
+                    .as_ref()
+                    .and_then(|t| t.typ.as_ref())
+                    .is_some_and(|t| matches!(t, Typ::Map(_)));
+                anyhow::ensure!(
+                    is_map,
+                    "field {} is marked as a catch-all sink but is not a map type",
+                    f.name
+                );
+
+                match wire_loc {
+                    WireLoc::Query => {
+                        anyhow::ensure!(
+                            query_sink.is_none(),
+                            "at most one catch-all query field is supported, found a second: {}",
+                            f.name
+                        );
+                        query_sink = Some(name.to_owned());
+                    }
+                    WireLoc::Header(_) => {
+                        anyhow::ensure!(
+                            header_sink.is_none(),
+                            "at most one catch-all header field is supported, found a second: {}",
+                            f.name
+                        );
+                        header_sink = Some(name.to_owned());
+                    }
+                    _ => unreachable!("catch_all is only set on query and header locations"),
+                }
+                continue;
+            }
+
+            // Lists routed to the query string or a header need a collection
+            // format so the encoder/decoder agree on how the elements are
+            // joined into a single wire value.
+            let is_list = f
+                .typ
+                .as_ref()
+                .and_then(|t| t.typ.as_ref())
+                .is_some_and(|t| list_elem_type(t).is_some());
+            let collection_format = if is_list {
+                CollectionFormat::default_for(&wire_loc)
+            } else {
+                None
+            };
+            field.collection_format = collection_format;
+
+            // Form fields that carry raw bytes are file parts (with a
+            // filename and content type) rather than plain key/value parts.
+            if matches!(wire_loc, WireLoc::Form) {
+                field.is_file_part = f
+                    .typ
+                    .as_ref()
+                    .and_then(|t| t.typ.as_ref())
+                    .is_some_and(is_file_field);
+            }
+
+            let is_header = matches!(&wire_loc, WireLoc::Header(_));
+            let is_cookie = matches!(&wire_loc, WireLoc::Cookie(_));
+
             // Add the field to the appropriate struct.
-            let (dst, name_override) = match wire_loc {
-                WireLoc::Body => (&mut body, None),
-                WireLoc::Query => (&mut query, None),
-                WireLoc::Header(s) => (&mut header, Some(s)),
+            let (dst, name_override, format_dst) = match wire_loc {
+                WireLoc::Body => (&mut body, None, None),
+                WireLoc::Query => (&mut query, None, Some(&mut query_formats)),
+                WireLoc::Header(s) => (&mut header, Some(s), Some(&mut header_formats)),
                 WireLoc::Path => unreachable!(),
-                WireLoc::Cookie(s) => (&mut cookie, Some(s)),
+                WireLoc::Cookie(s) => (&mut cookie, Some(s), None),
+                WireLoc::Form => (&mut form, None, None),
             };
-            field.name_override = name_override;
+            field.name_override = name_override.clone();
+
+            // Record the wire name for every header/cookie field (not just
+            // list-typed ones), so consumers working from the registry name
+            // alone (e.g. the OpenAPI emitter) can recover what's actually
+            // sent/received on the wire.
+            let wire_name = name_override.unwrap_or_else(|| name.to_owned());
+            if is_header {
+                anyhow::ensure!(
+                    !header_names.values().any(|v| v == &wire_name),
+                    "field {} collides with another header field on wire name {}",
+                    f.name,
+                    wire_name
+                );
+                header_names.insert(name.to_owned(), wire_name.clone());
+            }
+            if is_cookie {
+                anyhow::ensure!(
+                    !cookie_names.values().any(|v| v == &wire_name),
+                    "field {} collides with another cookie field on wire name {}",
+                    f.name,
+                    wire_name
+                );
+                cookie_names.insert(name.to_owned(), wire_name.clone());
+            }
+
+            if let (Some(format), Some(formats)) = (collection_format, format_dst) {
+                formats.insert(wire_name, format);
+            }
 
             match dst {
                 Some(dst) => {
@@ -178,7 +461,15 @@ impl EncodingConfig<'_, '_> {
             query: query.map(&mut build),
             header: header.map(&mut build),
             cookie: cookie.map(&mut build),
+            form: form.map(&mut build),
             rpc_path: self.rpc_path.cloned(),
+            query_formats,
+            header_formats,
+            header_names,
+            cookie_names,
+            query_sink,
+            header_sink,
+            body_codecs: self.body_codecs.clone(),
         })
     }
 
@@ -430,7 +721,15 @@ pub fn handshake_encoding(
                 query: None,
                 header: None,
                 cookie: None,
+                form: None,
                 rpc_path: Some(rpc_path.clone()),
+                query_formats: HashMap::new(),
+                query_sink: None,
+                header_sink: None,
+                body_codecs: Vec::new(),
+                header_formats: HashMap::new(),
+                header_names: HashMap::new(),
+                cookie_names: HashMap::new(),
             },
         }));
     };
@@ -444,6 +743,8 @@ pub fn handshake_encoding(
         supports_query: true,
         supports_header: true,
         supports_path: true,
+        supports_form: false,
+        body_codecs: Vec::new(),
     };
 
     let schema = config.compute(handshake_schema)?;
@@ -471,7 +772,15 @@ pub fn request_encoding(
                     query: None,
                     header: None,
                     cookie: None,
+                    form: None,
                     rpc_path: None,
+                    query_formats: HashMap::new(),
+                    query_sink: None,
+                    header_sink: None,
+                    body_codecs: Vec::new(),
+                    header_formats: HashMap::new(),
+                    header_names: HashMap::new(),
+                    cookie_names: HashMap::new(),
                 },
             }]);
         };
@@ -485,6 +794,8 @@ pub fn request_encoding(
             supports_query: false,
             supports_header: false,
             supports_path: false,
+            supports_form: false,
+            body_codecs: parse_body_codecs(&rpc.request_body_codecs),
         };
 
         let schema = config.compute(request_schema)?;
@@ -525,7 +836,15 @@ pub fn request_encoding(
                 query: None,
                 header: None,
                 cookie: None,
+                form: None,
                 rpc_path: Some(rpc_path.clone()),
+                query_formats: HashMap::new(),
+                query_sink: None,
+                header_sink: None,
+                body_codecs: Vec::new(),
+                header_formats: HashMap::new(),
+                header_names: HashMap::new(),
+                cookie_names: HashMap::new(),
             },
         }]);
     };
@@ -533,6 +852,9 @@ pub fn request_encoding(
     let mut schemas = Vec::new();
 
     for default_loc in split_by_loc(&methods) {
+        // The form/JSON choice for a `Body`-default group is resolved by
+        // `EncodingConfig::compute` from `supports_form`, so that the field
+        // is actually consulted rather than decided redundantly here.
         let mut config = EncodingConfig {
             meta,
             registry_builder,
@@ -542,6 +864,8 @@ pub fn request_encoding(
             supports_query: true,
             supports_header: true,
             supports_path: true,
+            supports_form: rpc.request_encoding_form,
+            body_codecs: parse_body_codecs(&rpc.request_body_codecs),
         };
         let schema = config.compute(request_schema)?;
         schemas.push(ReqSchemaUnderConstruction {
@@ -566,7 +890,15 @@ pub fn response_encoding(
             query: None,
             header: None,
             cookie: None,
+            form: None,
             rpc_path: None,
+            query_formats: HashMap::new(),
+            query_sink: None,
+            header_sink: None,
+            body_codecs: Vec::new(),
+            header_formats: HashMap::new(),
+            header_names: HashMap::new(),
+            cookie_names: HashMap::new(),
         });
     };
 
@@ -579,6 +911,8 @@ pub fn response_encoding(
         supports_query: false,
         supports_header: true,
         supports_path: false,
+        supports_form: false,
+        body_codecs: parse_body_codecs(&rpc.response_body_codecs),
     };
     config.compute(response_schema)
 }
@@ -596,3 +930,949 @@ fn split_by_loc(methods: &[Method]) -> impl Iterator<Item = (DefaultLoc, Vec<Met
 
     locs.into_iter()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collection_format_default_for_picks_per_location_defaults() {
+        assert_eq!(
+            CollectionFormat::default_for(&WireLoc::Query),
+            Some(CollectionFormat::Multi)
+        );
+        assert_eq!(
+            CollectionFormat::default_for(&WireLoc::Header("X".to_string())),
+            Some(CollectionFormat::Csv)
+        );
+        assert_eq!(CollectionFormat::default_for(&WireLoc::Body), None);
+        assert_eq!(CollectionFormat::default_for(&WireLoc::Path), None);
+        assert_eq!(
+            CollectionFormat::default_for(&WireLoc::Cookie("c".to_string())),
+            None
+        );
+        assert_eq!(CollectionFormat::default_for(&WireLoc::Form), None);
+    }
+
+    #[test]
+    fn collection_format_separator_matches_its_name() {
+        assert_eq!(CollectionFormat::Csv.separator(), Some(','));
+        assert_eq!(CollectionFormat::Ssv.separator(), Some(' '));
+        assert_eq!(CollectionFormat::Tsv.separator(), Some('\t'));
+        assert_eq!(CollectionFormat::Pipes.separator(), Some('|'));
+        assert_eq!(CollectionFormat::Multi.separator(), None);
+    }
+
+    fn builtin_field(name: &str, wire: Option<schema::WireSpec>) -> schema::Field {
+        schema::Field {
+            name: name.to_string(),
+            typ: Some(schema::Type {
+                typ: Some(Typ::Builtin(schema::Builtin::String as i32)),
+                validation: None,
+            }),
+            wire,
+            ..Default::default()
+        }
+    }
+
+    fn map_field(name: &str, wire: Option<schema::WireSpec>) -> schema::Field {
+        schema::Field {
+            name: name.to_string(),
+            typ: Some(schema::Type {
+                typ: Some(Typ::Map(Box::new(schema::Map {
+                    key: Some(Box::new(schema::Type {
+                        typ: Some(Typ::Builtin(schema::Builtin::String as i32)),
+                        validation: None,
+                    })),
+                    value: Some(Box::new(schema::Type {
+                        typ: Some(Typ::Builtin(schema::Builtin::String as i32)),
+                        validation: None,
+                    })),
+                }))),
+                validation: None,
+            }),
+            wire,
+            ..Default::default()
+        }
+    }
+
+    fn query_catch_all_wire() -> schema::WireSpec {
+        schema::WireSpec {
+            location: Some(schema::wire_spec::Location::Query(schema::wire_spec::Query {
+                catch_all: true,
+            })),
+        }
+    }
+
+    fn header_wire(name: Option<&str>) -> schema::WireSpec {
+        schema::WireSpec {
+            location: Some(schema::wire_spec::Location::Header(schema::wire_spec::Header {
+                name: name.map(str::to_string),
+                catch_all: false,
+            })),
+        }
+    }
+
+    fn compute_struct(
+        fields: Vec<schema::Field>,
+    ) -> anyhow::Result<SchemaUnderConstruction> {
+        let mut builder = jsonschema::Builder::default();
+        let mut config = EncodingConfig {
+            meta: &meta::Data::default(),
+            registry_builder: &mut builder,
+            default_loc: Some(DefaultLoc::Body),
+            rpc_path: None,
+            supports_body: true,
+            supports_query: true,
+            supports_header: true,
+            supports_path: false,
+            supports_form: false,
+            body_codecs: Vec::new(),
+        };
+        config.compute(&schema::Type {
+            typ: Some(Typ::Struct(schema::Struct { fields })),
+            validation: None,
+        })
+    }
+
+    #[test]
+    fn compute_rejects_a_non_map_catch_all_field() {
+        let err = compute_struct(vec![builtin_field("extra", Some(query_catch_all_wire()))])
+            .expect_err("non-map catch-all field should be rejected");
+        assert!(err.to_string().contains("not a map type"), "{err}");
+    }
+
+    #[test]
+    fn compute_rejects_a_second_catch_all_field_for_the_same_location() {
+        let err = compute_struct(vec![
+            map_field("extra", Some(query_catch_all_wire())),
+            map_field("more", Some(query_catch_all_wire())),
+        ])
+        .expect_err("a second catch-all field for the same location should be rejected");
+        assert!(err.to_string().contains("second"), "{err}");
+    }
+
+    #[test]
+    fn compute_rejects_two_header_fields_with_the_same_wire_name() {
+        let err = compute_struct(vec![
+            builtin_field("traceId", Some(header_wire(Some("X-Trace-Id")))),
+            builtin_field("requestTrace", Some(header_wire(Some("X-Trace-Id")))),
+        ])
+        .expect_err("two header fields sharing a wire name should be rejected");
+        assert!(err.to_string().contains("collides"), "{err}");
+    }
+
+    #[test]
+    fn is_file_field_recognizes_bytes_and_wrapped_bytes() {
+        let bytes = Typ::Builtin(schema::Builtin::Bytes as i32);
+        let string = Typ::Builtin(schema::Builtin::String as i32);
+
+        assert!(is_file_field(&bytes));
+        assert!(!is_file_field(&string));
+
+        let list_of_bytes = Typ::List(Box::new(schema::List {
+            elem: Some(Box::new(schema::Type {
+                typ: Some(bytes.clone()),
+                validation: None,
+            })),
+        }));
+        assert!(is_file_field(&list_of_bytes));
+
+        let pointer_to_bytes = Typ::Pointer(Box::new(schema::Pointer {
+            base: Some(Box::new(schema::Type {
+                typ: Some(bytes),
+                validation: None,
+            })),
+        }));
+        assert!(is_file_field(&pointer_to_bytes));
+
+        let list_of_strings = Typ::List(Box::new(schema::List {
+            elem: Some(Box::new(schema::Type {
+                typ: Some(string),
+                validation: None,
+            })),
+        }));
+        assert!(!is_file_field(&list_of_strings));
+    }
+
+    #[test]
+    fn body_codec_from_media_type_matches_case_insensitively_and_strips_charset() {
+        assert_eq!(
+            BodyCodec::from_media_type("application/json"),
+            Some(BodyCodec::Json)
+        );
+        assert_eq!(
+            BodyCodec::from_media_type("APPLICATION/JSON"),
+            Some(BodyCodec::Json)
+        );
+        assert_eq!(
+            BodyCodec::from_media_type("application/json; charset=utf-8"),
+            Some(BodyCodec::Json)
+        );
+        assert_eq!(
+            BodyCodec::from_media_type("application/x-www-form-urlencoded"),
+            Some(BodyCodec::FormUrlEncoded)
+        );
+        assert_eq!(BodyCodec::from_media_type("text/plain"), None);
+    }
+
+    #[test]
+    fn body_codec_negotiate_falls_back_to_json() {
+        // No Content-Type at all.
+        assert_eq!(BodyCodec::negotiate(None, &[BodyCodec::Yaml]), BodyCodec::Json);
+
+        // A codec the rpc doesn't support.
+        assert_eq!(
+            BodyCodec::negotiate(Some("application/yaml"), &[BodyCodec::MessagePack]),
+            BodyCodec::Json
+        );
+
+        // A supported codec is honored.
+        assert_eq!(
+            BodyCodec::negotiate(Some("application/yaml"), &[BodyCodec::Yaml]),
+            BodyCodec::Yaml
+        );
+
+        // `;charset` is stripped before matching.
+        assert_eq!(
+            BodyCodec::negotiate(Some("application/msgpack; charset=binary"), &[BodyCodec::MessagePack]),
+            BodyCodec::MessagePack
+        );
+    }
+}
+
+/// Generates an OpenAPI 3.0 document straight from the computed RPC
+/// encodings, so the emitted contract can never drift from what the
+/// runtime actually parses and serializes on the wire.
+pub mod openapi {
+    use super::*;
+    use serde::Serialize;
+    use serde_json::Value;
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct Document {
+        pub openapi: &'static str,
+        pub info: Info,
+        pub paths: HashMap<String, PathItem>,
+        pub components: Components,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct Info {
+        pub title: String,
+        pub version: String,
+    }
+
+    #[derive(Debug, Clone, Default, Serialize)]
+    pub struct PathItem {
+        #[serde(flatten)]
+        pub operations: HashMap<String, Operation>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct Operation {
+        #[serde(rename = "operationId")]
+        pub operation_id: String,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub parameters: Vec<Parameter>,
+        #[serde(rename = "requestBody", skip_serializing_if = "Option::is_none")]
+        pub request_body: Option<RequestBody>,
+        pub responses: HashMap<String, Response>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct Parameter {
+        pub name: String,
+        #[serde(rename = "in")]
+        pub location: &'static str,
+        pub required: bool,
+        pub schema: Value,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub style: Option<&'static str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub explode: Option<bool>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RequestBody {
+        pub required: bool,
+        pub content: HashMap<String, MediaType>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct Response {
+        pub description: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub content: Option<HashMap<String, MediaType>>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct MediaType {
+        pub schema: Value,
+    }
+
+    #[derive(Debug, Clone, Default, Serialize)]
+    pub struct Components {
+        pub schemas: HashMap<String, Value>,
+    }
+
+    struct PendingOp {
+        path: String,
+        method: Method,
+        base_operation_id: String,
+        req: SchemaUnderConstruction,
+        resp: SchemaUnderConstruction,
+    }
+
+    /// The registry indices and per-field wire metadata backing a built
+    /// `Schema`, captured before `SchemaUnderConstruction::build` consumes
+    /// them, so the emitted document can point back at the exact
+    /// `components/schemas` entry each field came from instead of
+    /// re-inlining it, and can render parameters under their wire names
+    /// and collection formats rather than their registry names.
+    #[derive(Debug, Clone, Default)]
+    struct SchemaMeta {
+        body: Option<usize>,
+        query: Option<usize>,
+        header: Option<usize>,
+        cookie: Option<usize>,
+        form: Option<usize>,
+        query_formats: HashMap<String, CollectionFormat>,
+        header_formats: HashMap<String, CollectionFormat>,
+        header_names: HashMap<String, String>,
+        cookie_names: HashMap<String, String>,
+    }
+
+    impl SchemaMeta {
+        fn from(s: &SchemaUnderConstruction) -> Self {
+            Self {
+                body: s.body,
+                query: s.query,
+                header: s.header,
+                cookie: s.cookie,
+                form: s.form,
+                query_formats: s.query_formats.clone(),
+                header_formats: s.header_formats.clone(),
+                header_names: s.header_names.clone(),
+                cookie_names: s.cookie_names.clone(),
+            }
+        }
+    }
+
+    /// Walks every RPC in `meta`, runs it through the same
+    /// `request_encoding`/`response_encoding` computations the runtime
+    /// uses, and assembles an OpenAPI document from the result.
+    pub fn generate(meta: &meta::Data) -> anyhow::Result<Document> {
+        let mut builder = jsonschema::Builder::default();
+        let mut pending = Vec::new();
+
+        for svc in &meta.svcs {
+            for rpc in &svc.rpcs {
+                let resp = response_encoding(&mut builder, meta, rpc).with_context(|| {
+                    format!("compute response encoding for {}.{}", rpc.service_name, rpc.name)
+                })?;
+                let reqs = request_encoding(&mut builder, meta, rpc).with_context(|| {
+                    format!("compute request encoding for {}.{}", rpc.service_name, rpc.name)
+                })?;
+
+                for req in reqs {
+                    let path = openapi_path(req.schema.rpc_path.as_ref())?;
+                    for method in req.methods.clone() {
+                        pending.push(PendingOp {
+                            path: path.clone(),
+                            method,
+                            base_operation_id: format!("{}.{}", rpc.service_name, rpc.name),
+                            req: req.schema.clone(),
+                            resp: resp.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let registry = builder.build().context("finalize schema registry")?;
+
+        let mut paths: HashMap<String, PathItem> = HashMap::new();
+        let mut schemas: HashMap<String, Value> = HashMap::new();
+        for op in pending {
+            let req_meta = SchemaMeta::from(&op.req);
+            let resp_meta = SchemaMeta::from(&op.resp);
+            let req_schema = op.req.build(&registry)?;
+            let resp_schema = op.resp.build(&registry)?;
+            let operation = build_operation(
+                op.method,
+                op.base_operation_id,
+                req_schema,
+                resp_schema,
+                req_meta,
+                resp_meta,
+                &registry,
+                &mut schemas,
+            )?;
+            paths
+                .entry(op.path)
+                .or_default()
+                .operations
+                .insert(method_key(op.method), operation);
+        }
+
+        Ok(Document {
+            openapi: "3.0.3",
+            info: Info {
+                title: "Encore API".to_string(),
+                // Placeholder until app metadata carries a real version.
+                version: "0.0.0".to_string(),
+            },
+            paths,
+            components: Components { schemas },
+        })
+    }
+
+    /// The lowercase method name used both as the `PathItem` operation key
+    /// and as the `operationId` suffix.
+    fn method_key(method: Method) -> String {
+        format!("{:?}", method).to_lowercase()
+    }
+
+    /// Registers the schema at `idx` under `components/schemas` the first
+    /// time it's seen and returns its component name, so operations that
+    /// share a registry entry (e.g. the same request struct reused across
+    /// RPCs) share a single `components/schemas` entry too.
+    fn register_component(
+        schemas: &mut HashMap<String, Value>,
+        registry: &Arc<jsonschema::Registry>,
+        idx: usize,
+    ) -> anyhow::Result<String> {
+        let name = format!("Schema{idx}");
+        if !schemas.contains_key(&name) {
+            let value = serde_json::to_value(registry.schema(idx)).context("serialize schema")?;
+            schemas.insert(name.clone(), value);
+        }
+        Ok(name)
+    }
+
+    /// A `{"$ref": "#/components/schemas/..."}` pointing at `name`.
+    fn ref_value(name: &str) -> Value {
+        serde_json::json!({ "$ref": format!("#/components/schemas/{name}") })
+    }
+
+    fn build_operation(
+        method: Method,
+        base_operation_id: String,
+        req: Schema,
+        resp: Schema,
+        req_meta: SchemaMeta,
+        resp_meta: SchemaMeta,
+        registry: &Arc<jsonschema::Registry>,
+        schemas: &mut HashMap<String, Value>,
+    ) -> anyhow::Result<Operation> {
+        let mut parameters = Vec::new();
+        if let Some(path) = &req.path {
+            // Path parameters are derived straight from the route, not from
+            // a struct in the schema registry, so there's no component to
+            // `$ref` and they stay inlined.
+            parameters.extend(schema_parameters(
+                path.schema(),
+                "path",
+                None,
+                None,
+                None,
+            )?);
+        }
+        if let Some(query) = &req.query {
+            let name = req_meta
+                .query
+                .map(|idx| register_component(schemas, registry, idx))
+                .transpose()?;
+            parameters.extend(schema_parameters(
+                query.schema(),
+                "query",
+                name.as_deref(),
+                None,
+                Some(&req_meta.query_formats),
+            )?);
+        }
+        if let Some(header) = &req.header {
+            let name = req_meta
+                .header
+                .map(|idx| register_component(schemas, registry, idx))
+                .transpose()?;
+            parameters.extend(schema_parameters(
+                header.schema(),
+                "header",
+                name.as_deref(),
+                Some(&req_meta.header_names),
+                Some(&req_meta.header_formats),
+            )?);
+        }
+        if let Some(cookie) = &req.cookie {
+            let name = req_meta
+                .cookie
+                .map(|idx| register_component(schemas, registry, idx))
+                .transpose()?;
+            parameters.extend(schema_parameters(
+                cookie.schema(),
+                "cookie",
+                name.as_deref(),
+                Some(&req_meta.cookie_names),
+                None,
+            )?);
+        }
+
+        let request_body = match (&req.body, &req.form) {
+            (Some(body), _) => {
+                let idx = req_meta
+                    .body
+                    .context("body schema without a registry entry")?;
+                let name = register_component(schemas, registry, idx)?;
+                Some(RequestBody {
+                    required: true,
+                    content: body_content(&name, body.codecs()),
+                })
+            }
+            // `form` (multipart/form-data, added for file-upload fields)
+            // takes over the request body slot that `body` would otherwise
+            // occupy when the rpc opts into form encoding.
+            (None, Some(_)) => {
+                let idx = req_meta
+                    .form
+                    .context("form schema without a registry entry")?;
+                let name = register_component(schemas, registry, idx)?;
+                Some(RequestBody {
+                    required: true,
+                    content: form_content(&name),
+                })
+            }
+            (None, None) => None,
+        };
+
+        let content = match &resp.body {
+            Some(body) => {
+                let idx = resp_meta
+                    .body
+                    .context("body schema without a registry entry")?;
+                let name = register_component(schemas, registry, idx)?;
+                Some(body_content(&name, body.codecs()))
+            }
+            None => None,
+        };
+
+        let mut responses = HashMap::new();
+        responses.insert(
+            "200".to_string(),
+            Response {
+                description: "OK".to_string(),
+                content,
+            },
+        );
+
+        Ok(Operation {
+            operation_id: format!("{}.{}", base_operation_id, method_key(method)),
+            parameters,
+            request_body,
+            responses,
+        })
+    }
+
+    /// Renders a `meta::Path` as an OpenAPI path template, e.g.
+    /// `/users/{id}`.
+    fn openapi_path(path: Option<&meta::Path>) -> anyhow::Result<String> {
+        let Some(path) = path else {
+            return Ok("/".to_string());
+        };
+
+        let mut out = String::new();
+        for seg in &path.segments {
+            out.push('/');
+            let typ = SegmentType::try_from(seg.r#type).context("invalid segment type")?;
+            match typ {
+                SegmentType::Literal => out.push_str(&seg.value),
+                SegmentType::Param | SegmentType::Wildcard | SegmentType::Fallback => {
+                    out.push('{');
+                    out.push_str(&seg.value);
+                    out.push('}');
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Breaks a query/header/path/cookie struct schema into individual
+    /// OpenAPI parameters. When `component` names the `components/schemas`
+    /// entry the struct was registered under, each parameter's schema is a
+    /// `$ref` into that entry's `properties` rather than a re-inlined copy.
+    ///
+    /// `name_overrides` maps a field's registry name to its wire name (set
+    /// for header/cookie fields with a `name_override`); when absent, the
+    /// registry name is also the wire name. `formats` maps wire names to
+    /// the `CollectionFormat` list-typed fields were routed through, used
+    /// to emit `style`/`explode`.
+    fn schema_parameters(
+        schema: &jsonschema::JSONSchema,
+        location: &'static str,
+        component: Option<&str>,
+        name_overrides: Option<&HashMap<String, String>>,
+        formats: Option<&HashMap<String, CollectionFormat>>,
+    ) -> anyhow::Result<Vec<Parameter>> {
+        let value = serde_json::to_value(schema).context("serialize schema")?;
+        let Some(properties) = value.get("properties").and_then(Value::as_object) else {
+            return Ok(Vec::new());
+        };
+        let required: HashSet<&str> = value
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|arr| arr.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        Ok(properties
+            .iter()
+            .map(|(name, field_schema)| {
+                let wire_name = name_overrides
+                    .and_then(|m| m.get(name))
+                    .cloned()
+                    .unwrap_or_else(|| name.clone());
+                let (style, explode) = formats
+                    .and_then(|f| f.get(&wire_name))
+                    .map(|format| collection_format_style(location, *format))
+                    .unwrap_or((None, None));
+
+                Parameter {
+                    name: wire_name,
+                    location,
+                    // Path parameters are mandatory by definition in OpenAPI
+                    // 3 regardless of what the schema's `required` array
+                    // says about the underlying struct field.
+                    required: location == "path" || required.contains(name.as_str()),
+                    schema: match component {
+                        Some(component) => ref_value(&format!(
+                            "{component}/properties/{}",
+                            json_pointer_escape(name)
+                        )),
+                        None => field_schema.clone(),
+                    },
+                    style,
+                    explode,
+                }
+            })
+            .collect())
+    }
+
+    /// Maps a list field's `CollectionFormat` to the OpenAPI 3 `style`/
+    /// `explode` pair that reproduces it on the wire. Header parameters
+    /// only support the `simple` style in OpenAPI 3, so every header
+    /// collection format maps to it; `Tsv` has no standard OpenAPI 3 query
+    /// style, so it falls back to `form`/non-exploded, the closest
+    /// available approximation.
+    fn collection_format_style(
+        location: &str,
+        format: CollectionFormat,
+    ) -> (Option<&'static str>, Option<bool>) {
+        if location == "header" {
+            return (Some("simple"), Some(false));
+        }
+        match format {
+            CollectionFormat::Csv | CollectionFormat::Tsv => (Some("form"), Some(false)),
+            CollectionFormat::Ssv => (Some("spaceDelimited"), Some(false)),
+            CollectionFormat::Pipes => (Some("pipeDelimited"), Some(false)),
+            CollectionFormat::Multi => (Some("form"), Some(true)),
+        }
+    }
+
+    /// Escapes a JSON Pointer reference token per RFC 6901 (`~` -> `~0`,
+    /// `/` -> `~1`), so a field name containing either character still
+    /// resolves to the right place in a `$ref`.
+    fn json_pointer_escape(token: &str) -> Cow<'_, str> {
+        if token.contains(['~', '/']) {
+            Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+        } else {
+            Cow::Borrowed(token)
+        }
+    }
+
+    /// Builds the `content` map for a body, listing every codec the rpc
+    /// negotiates in addition to the always-supported JSON fallback. They
+    /// all `$ref` the same component schema, since negotiation only changes
+    /// the wire format, not the shape of the data.
+    fn body_content(component: &str, codecs: &[BodyCodec]) -> HashMap<String, MediaType> {
+        let media_type = MediaType { schema: ref_value(component) };
+        let mut content = HashMap::new();
+        content.insert(BodyCodec::Json.media_type().to_string(), media_type.clone());
+        for codec in codecs {
+            content
+                .entry(codec.media_type().to_string())
+                .or_insert_with(|| media_type.clone());
+        }
+        content
+    }
+
+    /// Builds the `content` map for a form request body, accepting both
+    /// `multipart/form-data` (needed for file-upload fields) and
+    /// `application/x-www-form-urlencoded`. Both are listed unconditionally:
+    /// the document doesn't currently distinguish forms that carry a file
+    /// field (which a urlencoded body can't represent) from ones that don't.
+    fn form_content(component: &str) -> HashMap<String, MediaType> {
+        let media_type = MediaType { schema: ref_value(component) };
+        let mut content = HashMap::new();
+        content.insert("multipart/form-data".to_string(), media_type.clone());
+        content.insert(
+            BodyCodec::FormUrlEncoded.media_type().to_string(),
+            media_type,
+        );
+        content
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn builtin(b: schema::Builtin) -> schema::Type {
+            schema::Type {
+                typ: Some(Typ::Builtin(b as i32)),
+                validation: None,
+            }
+        }
+
+        fn field(name: &str, typ: schema::Type, wire: Option<schema::WireSpec>) -> schema::Field {
+            schema::Field {
+                name: name.to_string(),
+                typ: Some(typ),
+                wire,
+                ..Default::default()
+            }
+        }
+
+        fn struct_type(fields: Vec<schema::Field>) -> schema::Type {
+            schema::Type {
+                typ: Some(Typ::Struct(schema::Struct { fields })),
+                validation: None,
+            }
+        }
+
+        fn rpc_path(segments: Vec<(SegmentType, &str)>) -> meta::Path {
+            meta::Path {
+                segments: segments
+                    .into_iter()
+                    .map(|(typ, value)| meta::PathSegment {
+                        value: value.to_string(),
+                        r#type: typ as i32,
+                        value_type: meta::path_segment::ParamType::String as i32,
+                        validation: None,
+                    })
+                    .collect(),
+                r#type: meta::path::Type::Url as i32,
+            }
+        }
+
+        fn rpc(
+            service_name: &str,
+            name: &str,
+            http_methods: &[&str],
+            path: Option<meta::Path>,
+            request_schema: Option<schema::Type>,
+            response_schema: Option<schema::Type>,
+        ) -> meta::Rpc {
+            meta::Rpc {
+                service_name: service_name.to_string(),
+                name: name.to_string(),
+                http_methods: http_methods.iter().map(|m| m.to_string()).collect(),
+                path,
+                request_schema,
+                response_schema,
+                ..Default::default()
+            }
+        }
+
+        fn data(svc_name: &str, rpcs: Vec<meta::Rpc>) -> meta::Data {
+            meta::Data {
+                svcs: vec![meta::Svc {
+                    name: svc_name.to_string(),
+                    rpcs,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn generate_emits_component_refs_for_parameters() {
+            let request_schema = struct_type(vec![
+                field("id", builtin(schema::Builtin::String), None),
+                field(
+                    "filter",
+                    builtin(schema::Builtin::String),
+                    Some(schema::WireSpec {
+                        location: Some(schema::wire_spec::Location::Query(
+                            schema::wire_spec::Query { catch_all: false },
+                        )),
+                    }),
+                ),
+                field(
+                    "trace",
+                    builtin(schema::Builtin::String),
+                    Some(schema::WireSpec {
+                        location: Some(schema::wire_spec::Location::Header(
+                            schema::wire_spec::Header {
+                                name: None,
+                                catch_all: false,
+                            },
+                        )),
+                    }),
+                ),
+            ]);
+            let response_schema =
+                struct_type(vec![field("name", builtin(schema::Builtin::String), None)]);
+
+            let path = rpc_path(vec![
+                (SegmentType::Literal, "widgets"),
+                (SegmentType::Param, "id"),
+            ]);
+
+            let meta = data(
+                "svc",
+                vec![rpc(
+                    "svc",
+                    "Get",
+                    &["GET"],
+                    Some(path),
+                    Some(request_schema),
+                    Some(response_schema),
+                )],
+            );
+
+            let doc = generate(&meta).expect("generate should succeed");
+
+            let op = doc
+                .paths
+                .get("/widgets/{id}")
+                .expect("path should be present")
+                .operations
+                .get("get")
+                .expect("get operation should be present");
+
+            assert_eq!(op.operation_id, "svc.Get.get");
+
+            let path_param = op.parameters.iter().find(|p| p.name == "id").unwrap();
+            assert_eq!(path_param.location, "path");
+
+            let query_param = op.parameters.iter().find(|p| p.name == "filter").unwrap();
+            let query_ref = query_param
+                .schema
+                .get("$ref")
+                .and_then(Value::as_str)
+                .expect("query parameter should be a $ref");
+            assert!(query_ref.starts_with("#/components/schemas/"));
+
+            let header_param = op.parameters.iter().find(|p| p.name == "trace").unwrap();
+            assert!(header_param.schema.get("$ref").is_some());
+
+            // The referenced component actually exists in `components/schemas`.
+            let component_name = query_ref
+                .strip_prefix("#/components/schemas/")
+                .unwrap()
+                .split('/')
+                .next()
+                .unwrap();
+            assert!(doc.components.schemas.contains_key(component_name));
+        }
+
+        #[test]
+        fn generate_gives_each_http_method_a_unique_operation_id() {
+            let meta = data(
+                "svc",
+                vec![rpc("svc", "Multi", &["GET", "POST"], None, None, None)],
+            );
+
+            let doc = generate(&meta).expect("generate should succeed");
+            let path_item = doc
+                .paths
+                .values()
+                .next()
+                .expect("a path should have been generated");
+
+            assert_eq!(
+                path_item.operations.get("get").unwrap().operation_id,
+                "svc.Multi.get"
+            );
+            assert_eq!(
+                path_item.operations.get("post").unwrap().operation_id,
+                "svc.Multi.post"
+            );
+            assert_ne!(
+                path_item.operations.get("get").unwrap().operation_id,
+                path_item.operations.get("post").unwrap().operation_id
+            );
+        }
+
+        #[test]
+        fn openapi_path_renders_wildcard_as_a_plain_param() {
+            let p = rpc_path(vec![
+                (SegmentType::Literal, "files"),
+                (SegmentType::Wildcard, "rest"),
+            ]);
+            let rendered = openapi_path(Some(&p)).unwrap();
+            assert_eq!(rendered, "/files/{rest}");
+        }
+
+        #[test]
+        fn generate_names_header_and_path_parameters_correctly() {
+            let request_schema = struct_type(vec![
+                field("id", builtin(schema::Builtin::String), None),
+                field(
+                    "trace",
+                    builtin(schema::Builtin::String),
+                    Some(schema::WireSpec {
+                        location: Some(schema::wire_spec::Location::Header(
+                            schema::wire_spec::Header {
+                                name: Some("X-Trace-Id".to_string()),
+                                catch_all: false,
+                            },
+                        )),
+                    }),
+                ),
+            ]);
+
+            let path = rpc_path(vec![
+                (SegmentType::Literal, "widgets"),
+                (SegmentType::Param, "id"),
+            ]);
+
+            let meta = data(
+                "svc",
+                vec![rpc(
+                    "svc",
+                    "Get",
+                    &["GET"],
+                    Some(path),
+                    Some(request_schema),
+                    None,
+                )],
+            );
+
+            let doc = generate(&meta).expect("generate should succeed");
+            let op = doc
+                .paths
+                .get("/widgets/{id}")
+                .expect("path should be present")
+                .operations
+                .get("get")
+                .expect("get operation should be present");
+
+            // The header parameter must be named after the wire override,
+            // not the struct's registry field name.
+            assert!(op.parameters.iter().any(|p| p.name == "X-Trace-Id"));
+            assert!(!op.parameters.iter().any(|p| p.name == "trace"));
+
+            // Path parameters are always required, regardless of what the
+            // underlying struct's `required` array says.
+            let path_param = op.parameters.iter().find(|p| p.name == "id").unwrap();
+            assert!(path_param.required);
+        }
+
+        #[test]
+        fn form_content_accepts_multipart_and_urlencoded() {
+            let content = form_content("Schema0");
+            assert!(content.contains_key("multipart/form-data"));
+            assert!(content.contains_key("application/x-www-form-urlencoded"));
+        }
+    }
+}